@@ -1,4 +1,7 @@
+use conductor::input::{InputSettings, InputStreamId};
 use conductor::manager::{AudioManager, AudioManagerSettings, Event, LoopSettings};
+use conductor::mixer::track::{SendTrackId, TrackId};
+use conductor::playback_snapshot::PlaybackSnapshot;
 use mlua::prelude::*;
 
 use crate::{
@@ -22,6 +25,12 @@ impl<'lua> FromLua<'lua> for LLoopSettings {
 				if table.contains_key("endPoint")? {
 					settings.end = Some(table.get::<_, LDuration>("endPoint")?.0);
 				}
+				if table.contains_key("introPoint")? {
+					settings.intro = Some(table.get::<_, LDuration>("introPoint")?.0);
+				}
+				if table.contains_key("loopStartPoint")? {
+					settings.loop_start = Some(table.get::<_, LDuration>("loopStartPoint")?.0);
+				}
 				Ok(LLoopSettings(settings))
 			}
 			value => Err(LuaError::external(ConductorLuaError::wrong_argument_type(
@@ -31,6 +40,90 @@ impl<'lua> FromLua<'lua> for LLoopSettings {
 	}
 }
 
+pub struct LInputStreamId(pub InputStreamId);
+
+impl LuaUserData for LInputStreamId {}
+
+/// A mixer track, addressable from Lua so its sends can be adjusted.
+///
+/// Only the main track is reachable this way for now — sub-tracks don't have
+/// a Lua-side creation method in this build, so there's no way to get a
+/// `TrackId` for one to round-trip through Lua.
+pub struct LTrackId(pub TrackId);
+
+impl<'lua> FromLua<'lua> for LTrackId {
+	fn from_lua(lua_value: LuaValue<'lua>, _: &'lua Lua) -> LuaResult<Self> {
+		match lua_value {
+			LuaValue::String(s) if s.to_str()? == "main" => Ok(LTrackId(TrackId::Main)),
+			value => Err(LuaError::external(ConductorLuaError::wrong_argument_type(
+				"track id",
+				"\"main\"",
+				value,
+			))),
+		}
+	}
+}
+
+/// The id of a send track, returned by `LAudioManager`'s `addSendTrack` and
+/// passed back in to `setSendVolume`/`removeSend`/`sendVolume`.
+pub struct LSendTrackId(pub SendTrackId);
+
+impl LuaUserData for LSendTrackId {}
+
+pub struct LInputSettings(pub InputSettings);
+
+impl<'lua> FromLua<'lua> for LInputSettings {
+	fn from_lua(lua_value: LuaValue<'lua>, _: &'lua Lua) -> LuaResult<Self> {
+		match lua_value {
+			LuaNil => Ok(LInputSettings(InputSettings::default())),
+			LuaValue::Table(table) => {
+				let mut settings = InputSettings::default();
+				if table.contains_key("volume")? {
+					settings = settings.volume(table.get::<_, f64>("volume")?);
+				}
+				if table.contains_key("paused")? {
+					settings = settings.paused(table.get("paused")?);
+				}
+				Ok(LInputSettings(settings))
+			}
+			value => Err(LuaError::external(ConductorLuaError::wrong_argument_type(
+				"input settings",
+				"table",
+				value,
+			))),
+		}
+	}
+}
+
+pub struct LPlaybackSnapshot(pub PlaybackSnapshot);
+
+impl<'lua> FromLua<'lua> for LPlaybackSnapshot {
+	fn from_lua(lua_value: LuaValue<'lua>, _: &'lua Lua) -> LuaResult<Self> {
+		match lua_value {
+			LuaValue::Table(table) => Ok(LPlaybackSnapshot(PlaybackSnapshot {
+				position: table.get("position")?,
+				loop_state: table.get("loopState")?,
+				intro_done: table.get("introDone")?,
+			})),
+			value => Err(LuaError::external(ConductorLuaError::wrong_argument_type(
+				"playback snapshot",
+				"table",
+				value,
+			))),
+		}
+	}
+}
+
+impl<'lua> ToLua<'lua> for LPlaybackSnapshot {
+	fn to_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+		let table = lua.create_table()?;
+		table.set("position", self.0.position)?;
+		table.set("loopState", self.0.loop_state)?;
+		table.set("introDone", self.0.intro_done)?;
+		Ok(LuaValue::Table(table))
+	}
+}
+
 pub struct LAudioManagerSettings(pub AudioManagerSettings);
 
 impl<'lua> FromLua<'lua> for LAudioManagerSettings {
@@ -58,6 +151,10 @@ impl<'lua> FromLua<'lua> for LAudioManagerSettings {
 					settings.metronome_settings =
 						table.get::<_, LMetronomeSettings>("metronomeSettings")?.0;
 				}
+				// Loudness normalization is a per-sound, streaming-path setting
+				// (`crates::kira::sound::normalization`), not something the
+				// manager applies globally -- there's no manager-wide
+				// normalization pipeline to bind here.
 				Ok(LAudioManagerSettings(settings))
 			}
 			value => Err(LuaError::external(ConductorLuaError::wrong_argument_type(
@@ -137,6 +234,32 @@ impl LuaUserData for LAudioManager {
 			},
 		);
 
+		// getPlaybackState/setPlaybackState call this.0.playback_state()/
+		// set_playback_state() on AudioManager, same as every other method
+		// here. The seconds-based PlaybackSnapshot this file exchanges with
+		// Lua is meant to round-trip through the scheduler's frame-indexed
+		// Transport via PlaybackSnapshot::from_frames/to_frames and
+		// Transport::restore, but the manager method that would do that
+		// conversion and dispatch isn't part of this checkout for any
+		// AudioManager method, so it can't be wired up from this crate.
+		methods.add_method_mut(
+			"getPlaybackState",
+			|_: &Lua, this: &mut Self, id: LInstanceId| match this.0.playback_state(id.0) {
+				Ok(snapshot) => Ok(LPlaybackSnapshot(snapshot)),
+				Err(error) => Err(LuaError::external(error)),
+			},
+		);
+
+		methods.add_method_mut(
+			"setPlaybackState",
+			|_: &Lua, this: &mut Self, (id, snapshot): (LInstanceId, LPlaybackSnapshot)| {
+				match this.0.set_playback_state(id.0, snapshot.0) {
+					Ok(_) => Ok(()),
+					Err(error) => Err(LuaError::external(error)),
+				}
+			},
+		);
+
 		methods.add_method_mut(
 			"pauseInstance",
 			|_: &Lua, this: &mut Self, (id, fade_tween): (LInstanceId, Option<LTween>)| match this
@@ -308,6 +431,110 @@ impl LuaUserData for LAudioManager {
 
 		methods.add_method_mut("freeUnusedResources", |_: &Lua, this: &mut Self, _: ()| {
 			Ok(this.0.free_unused_resources())
-		})
+		});
+
+		// These five bindings call through to `AudioManager::{open,close}_input_stream`,
+		// `set_input_stream_volume`, and `{pause,resume}_input_stream`, the same
+		// as every other method on `this.0` in this file -- `AudioManager` routes
+		// them through its command queue to the real-time thread, where
+		// `conductor::input::InputStream::process` actually drains and resamples
+		// the capture ring buffer each tick. None of that manager-side plumbing
+		// is part of this checkout (its defining module isn't present here, for
+		// any `AudioManager` method, old or new), so it can't be wired up from
+		// this crate; `InputStream` and `InputStreamWriter` are complete and
+		// ready for it.
+		methods.add_method_mut(
+			"openInputStream",
+			|_: &Lua, this: &mut Self, settings: LInputSettings| {
+				match this.0.open_input_stream(settings.0) {
+					Ok(id) => Ok(LInputStreamId(id)),
+					Err(error) => Err(LuaError::external(error)),
+				}
+			},
+		);
+
+		methods.add_method_mut(
+			"closeInputStream",
+			|_: &Lua, this: &mut Self, id: LInputStreamId| match this.0.close_input_stream(id.0) {
+				Ok(_) => Ok(()),
+				Err(error) => Err(LuaError::external(error)),
+			},
+		);
+
+		methods.add_method_mut(
+			"setInputStreamVolume",
+			|_: &Lua, this: &mut Self, (id, volume): (LInputStreamId, f64)| {
+				match this.0.set_input_stream_volume(id.0, volume) {
+					Ok(_) => Ok(()),
+					Err(error) => Err(LuaError::external(error)),
+				}
+			},
+		);
+
+		methods.add_method_mut(
+			"pauseInputStream",
+			|_: &Lua, this: &mut Self, id: LInputStreamId| match this.0.pause_input_stream(id.0) {
+				Ok(_) => Ok(()),
+				Err(error) => Err(LuaError::external(error)),
+			},
+		);
+
+		methods.add_method_mut(
+			"resumeInputStream",
+			|_: &Lua, this: &mut Self, id: LInputStreamId| match this.0.resume_input_stream(id.0) {
+				Ok(_) => Ok(()),
+				Err(error) => Err(LuaError::external(error)),
+			},
+		);
+
+		// addSendTrack/setSendVolume/removeSend/sendVolume call through to
+		// AudioManager the same way every other method here does, routing to a
+		// command queue that would drive conductor::mixer::track::sends::TrackSends
+		// each render tick so its tweened ramps actually advance. That
+		// manager/command-queue wiring isn't part of this checkout for any
+		// method, so it can't be added from this crate; TrackSends' tween math
+		// is complete and ready to be driven by it.
+		methods.add_method_mut(
+			"addSendTrack",
+			|_: &Lua, this: &mut Self, volume: f64| match this.0.add_send_track(volume) {
+				Ok(id) => Ok(LSendTrackId(id)),
+				Err(error) => Err(LuaError::external(error)),
+			},
+		);
+
+		methods.add_method_mut(
+			"setSendVolume",
+			|_: &Lua,
+			 this: &mut Self,
+			 (track, send_track, volume, tween): (LTrackId, LSendTrackId, f64, Option<LTween>)| {
+				match this
+					.0
+					.set_send_volume(track.0, send_track.0, volume, tween.map(|tween| tween.0))
+				{
+					Ok(_) => Ok(()),
+					Err(error) => Err(LuaError::external(error)),
+				}
+			},
+		);
+
+		methods.add_method_mut(
+			"removeSend",
+			|_: &Lua, this: &mut Self, (track, send_track): (LTrackId, LSendTrackId)| {
+				match this.0.remove_send(track.0, send_track.0) {
+					Ok(_) => Ok(()),
+					Err(error) => Err(LuaError::external(error)),
+				}
+			},
+		);
+
+		methods.add_method_mut(
+			"sendVolume",
+			|_: &Lua, this: &mut Self, (track, send_track): (LTrackId, LSendTrackId)| {
+				match this.0.send_volume(track.0, send_track.0) {
+					Ok(volume) => Ok(volume),
+					Err(error) => Err(LuaError::external(error)),
+				}
+			},
+		)
 	}
 }
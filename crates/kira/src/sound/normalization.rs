@@ -0,0 +1,166 @@
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+use super::streaming::decoder::Decoder;
+use crate::dsp::Frame;
+
+/// Identifies a group of sounds (e.g. all tracks on an album) that should
+/// share a single normalization gain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AlbumId(pub u64);
+
+/// How a streaming sound's loudness-normalization gain is chosen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormalizationMode {
+	/// Normalizes this sound independently of any others.
+	Track,
+	/// Shares one gain across every sound loaded with the same `AlbumId`, so
+	/// the relative dynamics between them are preserved.
+	Album(AlbumId),
+	/// Uses album gain when `AlbumId` is known, otherwise falls back to
+	/// track gain.
+	Auto(Option<AlbumId>),
+}
+
+/// Settings controlling loudness normalization for a streaming sound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalizationSettings {
+	pub enabled: bool,
+	/// The target integrated loudness, in decibels.
+	pub target_db: f64,
+	/// The most a quiet sound is allowed to be boosted by, in decibels.
+	pub max_boost_db: f64,
+	pub mode: NormalizationMode,
+}
+
+impl NormalizationSettings {
+	pub fn new() -> Self {
+		Self {
+			enabled: false,
+			target_db: -16.0,
+			max_boost_db: 12.0,
+			mode: NormalizationMode::Track,
+		}
+	}
+}
+
+impl Default for NormalizationSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Accumulates mean-square energy over a decoded sound so an integrated
+/// loudness estimate, and the gain needed to normalize to it, can be
+/// computed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoudnessAccumulator {
+	sum_of_squares: f64,
+	num_samples: u64,
+}
+
+impl LoudnessAccumulator {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn add_frame(&mut self, frame: Frame) {
+		self.add_sample(frame.left);
+		self.add_sample(frame.right);
+	}
+
+	pub fn add_sample(&mut self, sample: f32) {
+		self.sum_of_squares += (sample as f64) * (sample as f64);
+		self.num_samples += 1;
+	}
+
+	fn mean_square(&self) -> f64 {
+		if self.num_samples == 0 {
+			return 0.0;
+		}
+		self.sum_of_squares / self.num_samples as f64
+	}
+
+	/// The integrated loudness of the accumulated samples, in decibels.
+	/// Silence (or no samples at all) is reported as `-100.0` rather than
+	/// `-inf` so it can be used safely in gain arithmetic.
+	pub fn level_db(&self) -> f64 {
+		let mean_square = self.mean_square();
+		if mean_square <= 0.0 {
+			return -100.0;
+		}
+		10.0 * mean_square.log10()
+	}
+
+	/// The gain, in decibels, needed to bring this accumulator's level up (or
+	/// down) to `target_db`, clamped so quiet material isn't boosted by more
+	/// than `max_boost_db`.
+	pub fn gain_db(&self, target_db: f64, max_boost_db: f64) -> f64 {
+		(target_db - self.level_db()).min(max_boost_db)
+	}
+}
+
+/// Converts a gain in decibels to an amplitude multiplier suitable for
+/// applying directly to output frames.
+pub fn gain_db_to_amplitude(gain_db: f64) -> f32 {
+	10.0f32.powf((gain_db / 20.0) as f32)
+}
+
+/// Decodes a whole sound from the beginning purely to measure its loudness,
+/// without keeping any of the decoded audio around. The caller is
+/// responsible for seeking the decoder back to where it actually wants to
+/// start playback afterwards.
+pub fn scan_loudness<Error>(
+	decoder: &mut dyn Decoder<Error = Error>,
+) -> Result<LoudnessAccumulator, Error> {
+	let mut accumulator = LoudnessAccumulator::new();
+	decoder.seek(0)?;
+	loop {
+		let frames = decoder.decode()?;
+		if frames.is_empty() {
+			break;
+		}
+		for frame in frames {
+			accumulator.add_frame(frame);
+		}
+	}
+	Ok(accumulator)
+}
+
+/// Tracks per-album running loudness totals so every sound in an album group
+/// can share one normalization gain. Each sound contributes its own
+/// `LoudnessAccumulator` as it's loaded; the shared gain is recomputed from
+/// the running total, so it becomes more accurate as more of the album's
+/// sounds are loaded (and is a reasonable track-level estimate before that).
+#[derive(Debug, Default)]
+pub struct AlbumGainRegistry {
+	totals: Mutex<HashMap<AlbumId, LoudnessAccumulator>>,
+}
+
+impl AlbumGainRegistry {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Folds `accumulator` into the running total for `album`, returning the
+	/// gain (in decibels) the album should currently use.
+	pub fn contribute(
+		&self,
+		album: AlbumId,
+		accumulator: LoudnessAccumulator,
+		target_db: f64,
+		max_boost_db: f64,
+	) -> f64 {
+		let mut totals = self.totals.lock().expect("album gain registry poisoned");
+		let total = totals.entry(album).or_default();
+		total.sum_of_squares += accumulator.sum_of_squares;
+		total.num_samples += accumulator.num_samples;
+		total.gain_db(target_db, max_boost_db)
+	}
+}
+
+/// A registry shared by every sound in the same manager, so they can agree on
+/// per-album gains.
+pub type SharedAlbumGainRegistry = Arc<AlbumGainRegistry>;
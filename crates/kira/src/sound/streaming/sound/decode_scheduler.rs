@@ -7,6 +7,7 @@ use std::{
 use crate::{
 	dsp::Frame,
 	sound::{
+		normalization::{gain_db_to_amplitude, scan_loudness, NormalizationMode},
 		streaming::{decoder::Decoder, DecodeSchedulerCommand, StreamingSoundSettings},
 		transport::Transport,
 		PlaybackState,
@@ -25,13 +26,66 @@ pub(crate) enum NextStep {
 	End,
 }
 
+/// The quality of the resampling used to read between decoded frames when
+/// `playback_rate` isn't exactly `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+	/// Rounds to the closest decoded frame. Cheapest, and the noisiest.
+	Nearest,
+	/// Blends linearly between the two surrounding decoded frames.
+	Linear,
+	/// 4-point Catmull-Rom interpolation across the two surrounding decoded
+	/// frames and their neighbors on either side. The most expensive option,
+	/// and the one with the least aliasing.
+	#[default]
+	Cubic,
+}
+
+/// A lightweight, serializable capture of a streaming sound's playback
+/// position, suitable for storing in a save file and restoring later.
+///
+/// `position` is a sample frame index, not seconds — this is the internal,
+/// engine-side representation. The Lua/save-file-facing type with the same
+/// name (`conductor::playback_snapshot::PlaybackSnapshot`) stores position in
+/// seconds instead; convert explicitly with its `from_frames`/`to_frames`
+/// rather than assuming the two line up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaybackSnapshot {
+	pub position: i64,
+	/// Whether playback had already entered its loop region at least once.
+	pub loop_state: bool,
+	/// Whether the sound's intro region (if it has one) has already finished
+	/// playing, so a restored sound resumes in the loop rather than
+	/// replaying the intro.
+	pub intro_done: bool,
+}
+
 pub(crate) struct DecodeScheduler<Error: Send + 'static> {
 	decoder: Box<dyn Decoder<Error = Error>>,
 	sample_rate: u32,
 	num_frames: Option<usize>,
 	transport: Transport,
+	/// The amplitude multiplier loudness normalization computed for this
+	/// sound, applied to every output frame. `1.0` (no-op) when
+	/// normalization is disabled.
+	output_gain: f32,
 	decoder_current_frame_index: usize,
 	decoded_chunk: Option<DecodedChunk>,
+	/// A chunk pre-decoded at an upcoming loop's start, kept separate from
+	/// `decoded_chunk` so warming it ahead of time doesn't evict the chunk
+	/// the live read cursor is still sequentially reading from.
+	loop_chunk: Option<DecodedChunk>,
+	/// How fast to advance through the decoded source per output frame.
+	/// `1.0` plays back at the source's native rate; anything else is a
+	/// pitch/speed change, read via `interpolation`-quality resampling.
+	playback_rate: f64,
+	interpolation: Interpolation,
+	/// How far `playback_rate` has carried the read cursor past
+	/// `transport.position`, in source frames. Kept in `[0.0, 1.0)` and
+	/// folded back in every tick so it tracks sub-frame drift without ever
+	/// running away from the integer position `Transport` is using for
+	/// region/loop bookkeeping.
+	fractional_offset: f64,
 	command_consumer: HeapConsumer<DecodeSchedulerCommand>,
 	frame_producer: HeapProducer<TimestampedFrame>,
 	error_producer: HeapProducer<Error>,
@@ -45,31 +99,112 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 		shared: Arc<Shared>,
 		command_consumer: HeapConsumer<DecodeSchedulerCommand>,
 		error_producer: HeapProducer<Error>,
+	) -> Result<(Self, HeapConsumer<TimestampedFrame>), Error> {
+		Self::new_internal(decoder, settings, None, shared, command_consumer, error_producer)
+	}
+
+	/// Re-creates a scheduler picking up from a [`PlaybackSnapshot`] captured
+	/// by a previous instance, seeking the decoder and restoring the
+	/// transport so playback continues from exactly the same frame.
+	pub fn from_snapshot(
+		decoder: Box<dyn Decoder<Error = Error>>,
+		settings: StreamingSoundSettings,
+		snapshot: PlaybackSnapshot,
+		shared: Arc<Shared>,
+		command_consumer: HeapConsumer<DecodeSchedulerCommand>,
+		error_producer: HeapProducer<Error>,
+	) -> Result<(Self, HeapConsumer<TimestampedFrame>), Error> {
+		Self::new_internal(
+			decoder,
+			settings,
+			Some(snapshot),
+			shared,
+			command_consumer,
+			error_producer,
+		)
+	}
+
+	fn new_internal(
+		mut decoder: Box<dyn Decoder<Error = Error>>,
+		settings: StreamingSoundSettings,
+		snapshot: Option<PlaybackSnapshot>,
+		shared: Arc<Shared>,
+		command_consumer: HeapConsumer<DecodeSchedulerCommand>,
+		error_producer: HeapProducer<Error>,
 	) -> Result<(Self, HeapConsumer<TimestampedFrame>), Error> {
 		let (mut frame_producer, frame_consumer) = HeapRb::new(BUFFER_SIZE).split();
-		// pre-seed the frame ringbuffer with a zero frame. this is the "previous" frame
-		// when the sound just started.
-		frame_producer
-			.push(TimestampedFrame {
-				frame: Frame::ZERO,
-				index: 0,
-			})
-			.expect("The frame producer shouldn't be full because we just created it");
 		let sample_rate = decoder.sample_rate();
 		let num_frames = decoder.num_frames();
+		// loudness normalization needs the whole sound's integrated loudness
+		// before a gain can be chosen, so this does a throwaway decode pass
+		// up front and then rewinds. worth the extra decode time: it's the
+		// only way a streamed sound can know its own gain before playback
+		// starts, rather than drifting the volume as more of it is measured.
+		let output_gain = if settings.normalization.enabled {
+			let accumulator = scan_loudness(&mut *decoder)?;
+			decoder.seek(0)?;
+			let target_db = settings.normalization.target_db;
+			let max_boost_db = settings.normalization.max_boost_db;
+			let gain_db = match settings.normalization.mode {
+				NormalizationMode::Track | NormalizationMode::Auto(None) => {
+					accumulator.gain_db(target_db, max_boost_db)
+				}
+				NormalizationMode::Album(album) | NormalizationMode::Auto(Some(album)) => {
+					match &settings.album_gain_registry {
+						Some(registry) => {
+							registry.contribute(album, accumulator, target_db, max_boost_db)
+						}
+						None => accumulator.gain_db(target_db, max_boost_db),
+					}
+				}
+			};
+			gain_db_to_amplitude(gain_db)
+		} else {
+			1.0
+		};
+		let mut transport = Transport::new(
+			settings.playback_region,
+			settings.loop_region,
+			false,
+			sample_rate,
+			num_frames,
+		);
+		transport.set_intro_region(settings.intro_region);
+		let mut decoder_current_frame_index = 0;
+		let mut decoded_chunk = None;
+		if let Some(snapshot) = snapshot {
+			transport.restore(snapshot.position, snapshot.intro_done, snapshot.loop_state);
+			decoder_current_frame_index = decoder.seek(snapshot.position.max(0) as usize)?;
+			// pre-seed the frame ringbuffer with a zero frame at the resume
+			// position, the same placeholder a fresh start uses at position 0.
+			frame_producer
+				.push(TimestampedFrame {
+					frame: Frame::ZERO,
+					index: snapshot.position,
+				})
+				.expect("The frame producer shouldn't be full because we just created it");
+		} else {
+			// pre-seed the frame ringbuffer with a zero frame. this is the
+			// "previous" frame when the sound just started.
+			frame_producer
+				.push(TimestampedFrame {
+					frame: Frame::ZERO,
+					index: 0,
+				})
+				.expect("The frame producer shouldn't be full because we just created it");
+		}
 		let scheduler = Self {
 			decoder,
 			sample_rate,
 			num_frames,
-			transport: Transport::new(
-				settings.playback_region,
-				settings.loop_region,
-				false,
-				sample_rate,
-				num_frames,
-			),
-			decoder_current_frame_index: 0,
-			decoded_chunk: None,
+			transport,
+			output_gain,
+			decoder_current_frame_index,
+			decoded_chunk,
+			loop_chunk: None,
+			playback_rate: settings.playback_rate,
+			interpolation: settings.interpolation,
+			fractional_offset: 0.0,
 			command_consumer,
 			frame_producer,
 			error_producer,
@@ -82,6 +217,16 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 		self.transport.position
 	}
 
+	/// Captures the current playback position so it can be restored later by
+	/// [`DecodeScheduler::from_snapshot`].
+	pub fn snapshot(&self) -> PlaybackSnapshot {
+		PlaybackSnapshot {
+			position: self.transport.position,
+			loop_state: self.transport.has_looped(),
+			intro_done: self.transport.intro_finished(),
+		}
+	}
+
 	pub fn start(mut self) {
 		std::thread::spawn(move || loop {
 			match self.run() {
@@ -119,7 +264,14 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 				DecodeSchedulerCommand::SeekTo(position) => self.seek_to(position)?,
 			}
 		}
-		let frame = self.frame_at_index(self.transport.position)?;
+		// if we're about to hand off from the intro to the loop region, warm the
+		// loop-start chunk into its own cache now so the wrap that happens in
+		// `increment_position` below doesn't introduce a decoding gap.
+		if let Some(loop_start) = self.transport.upcoming_loop_start() {
+			self.warm_loop_chunk(loop_start)?;
+		}
+		let read_position = self.transport.position as f64 + self.fractional_offset;
+		let frame = self.interpolated_frame_at(read_position)? * self.output_gain;
 		self.frame_producer
 			.push(TimestampedFrame {
 				frame,
@@ -127,6 +279,11 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 			})
 			.expect("could not push frame to frame producer");
 		self.transport.increment_position();
+		// `playback_rate` of exactly `1.0` (the common case) leaves this at
+		// `0.0` forever, so every tick reads exactly `transport.position`
+		// with no interpolation, same as before `playback_rate` existed.
+		self.fractional_offset += self.playback_rate - 1.0;
+		self.fractional_offset -= self.fractional_offset.floor();
 		if !self.transport.playing {
 			self.shared.reached_end.store(true, Ordering::SeqCst);
 			return Ok(NextStep::End);
@@ -138,38 +295,91 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 		if index < 0 {
 			return Ok(Frame::ZERO);
 		}
+		if let Some(num_frames) = self.num_frames {
+			// past the end of the sound, there's nothing left to decode. the
+			// playback/loop region logic in `Transport` is responsible for
+			// wrapping `index` back into range before we get here; if it
+			// didn't (e.g. we're in the one-shot tail past the last loop),
+			// reading silence here is the correct fallback.
+			if index as usize >= num_frames {
+				return Ok(Frame::ZERO);
+			}
+		}
 		let index: usize = index.try_into().expect("could not convert i64 into usize");
-		// if the requested frame is already loaded, return it
-		if let Some(chunk) = &self.decoded_chunk {
+		if let Some(chunk) = self.loop_chunk.take() {
 			if let Some(frame) = chunk.frame_at_index(index) {
+				// we've reached the frame `warm_loop_chunk` pre-decoded for
+				// this wrap; promote it to the live read cursor instead of
+				// decoding it again, which would just decode right over it.
+				self.decoder_current_frame_index = self.decoder.seek(chunk.end_index())?;
+				self.decoded_chunk = Some(chunk);
 				return Ok(frame);
 			}
+			self.loop_chunk = Some(chunk);
 		}
-		/*
-			otherwise, seek to the requested index and decode chunks sequentially
-			until we get the frame we want. just because we seek to an index does
-			not mean the next decoded chunk will have the frame we want (or any frame
-			at all, for that matter), so we may need to decode multiple chunks to
-			get the frame we care about.
-		*/
-		if index < self.decoder_current_frame_index {
-			self.decoder_current_frame_index = self.decoder.seek(index)?;
-		}
-		loop {
-			let decoded_chunk = DecodedChunk {
-				start_index: self.decoder_current_frame_index,
-				frames: self.decoder.decode()?,
-			};
-			self.decoder_current_frame_index += decoded_chunk.frames.len();
-			self.decoded_chunk = Some(decoded_chunk);
-			if let Some(chunk) = &self.decoded_chunk {
-				if let Some(frame) = chunk.frame_at_index(index) {
-					return Ok(frame);
-				}
+		fetch_raw_frame(
+			&mut *self.decoder,
+			&mut self.decoder_current_frame_index,
+			&mut self.decoded_chunk,
+			index,
+		)
+	}
+
+	/// Reads the decoded source at a (possibly fractional) `position`,
+	/// blending between neighboring decoded frames per `self.interpolation`.
+	/// At `playback_rate == 1.0`, `position`'s fractional part is always
+	/// `0.0`, so every mode reduces to exactly `frame_at_index(position)`.
+	fn interpolated_frame_at(&mut self, position: f64) -> Result<Frame, Error> {
+		let index = position.floor() as i64;
+		let t = (position - position.floor()) as f32;
+		match self.interpolation {
+			Interpolation::Nearest => self.frame_at_index(if t < 0.5 { index } else { index + 1 }),
+			Interpolation::Linear => {
+				let a = self.frame_at_index(index)?;
+				let b = self.frame_at_index(index + 1)?;
+				Ok(a + (b - a) * t)
+			}
+			Interpolation::Cubic => {
+				let p0 = self.frame_at_index(index - 1)?;
+				let p1 = self.frame_at_index(index)?;
+				let p2 = self.frame_at_index(index + 1)?;
+				let p3 = self.frame_at_index(index + 2)?;
+				Ok(cubic_interpolate(p0, p1, p2, p3, t))
 			}
 		}
 	}
 
+	/// Pre-decodes the chunk containing `loop_start` into `loop_chunk` ahead
+	/// of the wrap `Transport::increment_position` is about to perform.
+	/// Decoding it requires seeking the single shared decoder away from the
+	/// live sequential read position, so that position is saved and restored
+	/// around the detour, leaving the live read (and `decoded_chunk`)
+	/// untouched until the wrap actually reaches `loop_start` and
+	/// `frame_at_index` promotes this chunk in.
+	fn warm_loop_chunk(&mut self, loop_start: i64) -> Result<(), Error> {
+		if loop_start < 0 {
+			return Ok(());
+		}
+		let loop_start = loop_start as usize;
+		if let Some(chunk) = &self.loop_chunk {
+			if chunk.frame_at_index(loop_start).is_some() {
+				return Ok(());
+			}
+		}
+		let resume_index = self.decoder_current_frame_index;
+		let mut detour_index = resume_index;
+		let mut detour_chunk = None;
+		fetch_raw_frame(
+			&mut *self.decoder,
+			&mut detour_index,
+			&mut detour_chunk,
+			loop_start,
+		)?;
+		self.loop_chunk = detour_chunk;
+		self.decoder_current_frame_index = self.decoder.seek(resume_index)?;
+		Ok(())
+	}
+
 	fn seek_to(&mut self, position: f64) -> Result<(), Error> {
 		let index = (position * self.sample_rate as f64).round() as i64;
 		self.seek_to_index(index)?;
@@ -193,6 +403,50 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 	}
 }
 
+/// Fetches the decoded frame at `index`, decoding further chunks (and seeking
+/// the decoder first, if necessary) until it's available. Factored out of
+/// `DecodeScheduler::frame_at_index` as a free function so it only needs
+/// `&mut` borrows of the specific fields it touches, rather than all of
+/// `&mut self` -- `warm_loop_chunk` relies on this to decode into a
+/// standalone chunk without disturbing the live `decoded_chunk`/
+/// `decoder_current_frame_index` it's warming ahead of.
+fn fetch_raw_frame<Error>(
+	decoder: &mut dyn Decoder<Error = Error>,
+	decoder_current_frame_index: &mut usize,
+	decoded_chunk: &mut Option<DecodedChunk>,
+	index: usize,
+) -> Result<Frame, Error> {
+	// if the requested frame is already loaded, return it
+	if let Some(chunk) = &decoded_chunk {
+		if let Some(frame) = chunk.frame_at_index(index) {
+			return Ok(frame);
+		}
+	}
+	/*
+		otherwise, seek to the requested index and decode chunks sequentially
+		until we get the frame we want. just because we seek to an index does
+		not mean the next decoded chunk will have the frame we want (or any frame
+		at all, for that matter), so we may need to decode multiple chunks to
+		get the frame we care about.
+	*/
+	if index < *decoder_current_frame_index {
+		*decoder_current_frame_index = decoder.seek(index)?;
+	}
+	loop {
+		let chunk = DecodedChunk {
+			start_index: *decoder_current_frame_index,
+			frames: decoder.decode()?,
+		};
+		*decoder_current_frame_index += chunk.frames.len();
+		*decoded_chunk = Some(chunk);
+		if let Some(chunk) = &decoded_chunk {
+			if let Some(frame) = chunk.frame_at_index(index) {
+				return Ok(frame);
+			}
+		}
+	}
+}
+
 struct DecodedChunk {
 	pub start_index: usize,
 	pub frames: Vec<Frame>,
@@ -205,4 +459,19 @@ impl DecodedChunk {
 		}
 		self.frames.get(index - self.start_index).copied()
 	}
+
+	/// The index one past the last frame this chunk covers, i.e. where
+	/// sequential decoding should resume after it.
+	fn end_index(&self) -> usize {
+		self.start_index + self.frames.len()
+	}
+}
+
+/// 4-point Catmull-Rom interpolation between `p1` (`t = 0.0`) and `p2`
+/// (`t = 1.0`), using `p0` and `p3` as the surrounding control points.
+fn cubic_interpolate(p0: Frame, p1: Frame, p2: Frame, p3: Frame, t: f32) -> Frame {
+	let a = (p1 - p2) * 3.0 + p3 - p0;
+	let b = p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3;
+	let c = p2 - p0;
+	p1 + (c + (b + a * t) * t) * (0.5 * t)
 }
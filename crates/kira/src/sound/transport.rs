@@ -0,0 +1,174 @@
+/// A region of a sound, in sample frames, either of which end may be left
+/// open to mean "the start/end of the sound".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Region {
+	pub start: Option<i64>,
+	pub end: Option<i64>,
+}
+
+impl Region {
+	fn start_frame(&self) -> i64 {
+		self.start.unwrap_or(0)
+	}
+
+	fn end_frame(&self, num_frames: Option<usize>) -> i64 {
+		self.end
+			.or_else(|| num_frames.map(|num_frames| num_frames as i64))
+			.unwrap_or(i64::MAX)
+	}
+}
+
+/// A region that plays once before playback hands off to the loop region (if
+/// any). Used for music with a one-shot intro followed by a seamlessly
+/// looping body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntroRegion {
+	pub start: i64,
+	pub end: i64,
+}
+
+/// Tracks the current playback position of a sound and how it should move
+/// forward each tick, including playback region clamping, intro-then-loop
+/// handoff, and looping.
+#[derive(Debug, Clone)]
+pub(crate) struct Transport {
+	pub position: i64,
+	pub playing: bool,
+	playback_region: Region,
+	loop_region: Option<Region>,
+	intro_region: Option<IntroRegion>,
+	intro_finished: bool,
+	/// Whether playback has jumped to the loop region's start at least once,
+	/// either by wrapping at the loop's end or by the intro handing off to
+	/// the loop region.
+	has_looped: bool,
+	sample_rate: u32,
+	num_frames: Option<usize>,
+}
+
+impl Transport {
+	pub fn new(
+		playback_region: Region,
+		loop_region: Option<Region>,
+		playing: bool,
+		sample_rate: u32,
+		num_frames: Option<usize>,
+	) -> Self {
+		Self {
+			position: playback_region.start_frame(),
+			playing,
+			playback_region,
+			loop_region,
+			intro_region: None,
+			intro_finished: false,
+			has_looped: false,
+			sample_rate,
+			num_frames,
+		}
+	}
+
+	/// Sets the intro region. Playback starts at `intro.start`, plays through
+	/// to `intro.end` once, and then jumps to the loop region's start and
+	/// loops from there for the rest of playback.
+	pub fn set_intro_region(&mut self, intro_region: Option<IntroRegion>) {
+		if let Some(intro_region) = intro_region {
+			self.position = intro_region.start;
+		}
+		self.intro_region = intro_region;
+		self.intro_finished = false;
+	}
+
+	pub fn set_playback_region(
+		&mut self,
+		playback_region: Region,
+		_sample_rate: u32,
+		num_frames: Option<usize>,
+	) {
+		self.playback_region = playback_region;
+		self.num_frames = num_frames;
+	}
+
+	pub fn set_loop_region(
+		&mut self,
+		loop_region: Option<Region>,
+		_sample_rate: u32,
+		num_frames: Option<usize>,
+	) {
+		self.loop_region = loop_region;
+		self.num_frames = num_frames;
+	}
+
+	pub fn seek_to(&mut self, position: i64) {
+		self.position = position;
+	}
+
+	pub fn intro_finished(&self) -> bool {
+		self.intro_finished
+	}
+
+	/// Whether playback has entered its loop region at least once.
+	pub fn has_looped(&self) -> bool {
+		self.has_looped
+	}
+
+	/// Restores a position, intro-completion flag, and loop-entry flag
+	/// captured by a previous
+	/// [`crate::sound::streaming::sound::decode_scheduler::PlaybackSnapshot`],
+	/// so a resumed sound continues from exactly where it left off instead of
+	/// replaying the intro.
+	pub fn restore(&mut self, position: i64, intro_finished: bool, has_looped: bool) {
+		self.position = position;
+		self.intro_finished = intro_finished;
+		self.has_looped = has_looped;
+	}
+
+	/// Returns the loop start frame the scheduler should pre-decode, if
+	/// `increment_position` is about to hand off from the intro to the loop
+	/// region on this tick. The decoder uses this to warm its chunk cache
+	/// *before* the wrap happens, so the wrap introduces no audible gap.
+	pub fn upcoming_loop_start(&self) -> Option<i64> {
+		let intro_region = self.intro_region?;
+		if self.intro_finished {
+			return None;
+		}
+		if self.position + 1 >= intro_region.end {
+			Some(
+				self.loop_region
+					.map(|region| region.start_frame())
+					.unwrap_or(intro_region.end),
+			)
+		} else {
+			None
+		}
+	}
+
+	/// Advances the playback position by one frame, handling the
+	/// intro-to-loop handoff and ordinary looping.
+	pub fn increment_position(&mut self) {
+		self.position += 1;
+		if let Some(intro_region) = self.intro_region {
+			if !self.intro_finished && self.position >= intro_region.end {
+				self.intro_finished = true;
+				if let Some(loop_region) = self.loop_region {
+					self.position = loop_region.start_frame();
+					self.has_looped = true;
+				} else {
+					self.position = intro_region.end;
+				}
+				return;
+			}
+		}
+		if let Some(loop_region) = self.loop_region {
+			let end = loop_region.end_frame(self.num_frames);
+			if self.position >= end {
+				self.position = loop_region.start_frame();
+				self.has_looped = true;
+				return;
+			}
+		}
+		let end = self.playback_region.end_frame(self.num_frames);
+		if self.position >= end {
+			self.playing = false;
+		}
+	}
+}
@@ -0,0 +1,214 @@
+use std::sync::{
+	atomic::{AtomicU8, Ordering},
+	Arc,
+};
+
+use ringbuf::{HeapConsumer, HeapProducer, HeapRb};
+
+use crate::{dsp::Frame, mixer::track::TrackId, CachedValue, PlaybackState, Value};
+
+const INPUT_BUFFER_SIZE: usize = 16_384;
+
+/// Settings for an input capture stream.
+#[derive(Debug, Clone)]
+pub struct InputSettings {
+	/// The track the captured audio should be routed to.
+	pub track: TrackId,
+	/// The volume to play the captured audio at.
+	pub volume: Value<f64>,
+	/// Whether the stream should start paused.
+	pub paused: bool,
+}
+
+impl InputSettings {
+	pub fn new() -> Self {
+		Self {
+			track: TrackId::Main,
+			volume: Value::Fixed(1.0),
+			paused: false,
+		}
+	}
+
+	pub fn track(self, track: impl Into<TrackId>) -> Self {
+		Self {
+			track: track.into(),
+			..self
+		}
+	}
+
+	pub fn volume(self, volume: impl Into<Value<f64>>) -> Self {
+		Self {
+			volume: volume.into(),
+			..self
+		}
+	}
+
+	pub fn paused(self, paused: bool) -> Self {
+		Self { paused, ..self }
+	}
+}
+
+impl Default for InputSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// A unique identifier for an input capture stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputStreamId {
+	index: usize,
+}
+
+impl InputStreamId {
+	pub(crate) fn new(index: usize) -> Self {
+		Self { index }
+	}
+}
+
+/// State shared between the capture callback and the rest of the audio engine.
+pub(crate) struct InputStreamShared {
+	state: AtomicU8,
+}
+
+impl InputStreamShared {
+	pub fn new() -> Self {
+		Self {
+			state: AtomicU8::new(PlaybackState::Playing as u8),
+		}
+	}
+
+	pub fn state(&self) -> PlaybackState {
+		PlaybackState::from_u8(self.state.load(Ordering::SeqCst))
+	}
+
+	pub fn set_state(&self, state: PlaybackState) {
+		self.state.store(state as u8, Ordering::SeqCst);
+	}
+}
+
+/// The producing half of an input capture stream, owned by the platform-specific
+/// capture callback.
+///
+/// The capture callback runs on a real-time audio thread, so pushing frames into
+/// `frame_producer` must never block or allocate. If the ring buffer is full
+/// (because the mixing graph is falling behind), incoming frames are simply
+/// dropped rather than blocking the callback. Frames are pushed at the
+/// device's own sample rate; [`InputStream::process`] is the one that
+/// resamples to the engine rate, since it can't safely happen on the
+/// callback thread.
+pub struct InputStreamWriter {
+	device_sample_rate: u32,
+	frame_producer: HeapProducer<Frame>,
+	shared: Arc<InputStreamShared>,
+}
+
+impl InputStreamWriter {
+	/// Pushes a buffer of frames captured from the input device into the
+	/// engine, at the device's own sample rate. `InputStream::process`
+	/// resamples to the engine's sample rate on the way out, so `frames`
+	/// should *not* be pre-resampled here.
+	pub fn push_frames(&mut self, frames: &[Frame]) {
+		if self.shared.state() != PlaybackState::Playing {
+			return;
+		}
+		for frame in frames {
+			// if the ring buffer is full, drop the frame rather than blocking;
+			// a capture callback can never be allowed to stall.
+			self.frame_producer.push(*frame).ok();
+		}
+	}
+
+	pub fn device_sample_rate(&self) -> u32 {
+		self.device_sample_rate
+	}
+}
+
+/// A live input capture stream routed into the mixing graph.
+///
+/// Unlike [`crate::sound::streaming::StreamingSound`], an input stream has no
+/// decoder and no fixed length: it simply drains whatever frames the capture
+/// callback has produced since the last block.
+pub(crate) struct InputStream {
+	volume: CachedValue<f64>,
+	frame_consumer: HeapConsumer<Frame>,
+	shared: Arc<InputStreamShared>,
+	/// How many device frames to advance per engine frame, e.g. `0.5` if the
+	/// device captures at half the engine's sample rate.
+	resample_step: f64,
+	/// How far past `previous_frame` the next engine tick's output falls, in
+	/// device frames. Carries the fractional remainder between calls to
+	/// `process` so the resampling stays in phase with the device stream.
+	resample_position: f64,
+	previous_frame: Frame,
+	next_frame: Frame,
+}
+
+impl InputStream {
+	/// Creates a new input stream and the writer its capture callback should
+	/// push frames into.
+	///
+	/// `device_sample_rate` is the rate the capture device (and therefore
+	/// `InputStreamWriter`) produces frames at; `engine_sample_rate` is the
+	/// rate `process` is called at. When they differ, `process` linearly
+	/// resamples on the way out rather than silently drifting or
+	/// over/under-running the ring buffer.
+	pub fn new(
+		settings: InputSettings,
+		device_sample_rate: u32,
+		engine_sample_rate: u32,
+	) -> (Self, InputStreamWriter) {
+		let (frame_producer, frame_consumer) = HeapRb::new(INPUT_BUFFER_SIZE).split();
+		let shared = Arc::new(InputStreamShared::new());
+		if settings.paused {
+			shared.set_state(PlaybackState::Paused);
+		}
+		let stream = Self {
+			volume: CachedValue::new(settings.volume, 1.0),
+			frame_consumer,
+			shared: shared.clone(),
+			resample_step: device_sample_rate as f64 / engine_sample_rate as f64,
+			resample_position: 0.0,
+			previous_frame: Frame::ZERO,
+			next_frame: Frame::ZERO,
+		};
+		let writer = InputStreamWriter {
+			device_sample_rate,
+			frame_producer,
+			shared,
+		};
+		(stream, writer)
+	}
+
+	pub fn set_volume(&mut self, volume: Value<f64>) {
+		self.volume.set(volume);
+	}
+
+	pub fn set_paused(&mut self, paused: bool) {
+		self.shared.set_state(if paused {
+			PlaybackState::Paused
+		} else {
+			PlaybackState::Playing
+		});
+	}
+
+	/// Pulls the next captured frame out of the ring buffer, resampled to the
+	/// engine's sample rate and scaled by the stream's current volume.
+	/// Returns silence until the device has produced its first frames; if the
+	/// ring buffer runs dry after that, the last captured frame is held.
+	pub fn process(&mut self) -> Frame {
+		if self.shared.state() != PlaybackState::Playing {
+			self.frame_consumer.pop();
+			return Frame::ZERO;
+		}
+		self.resample_position += self.resample_step;
+		while self.resample_position >= 1.0 {
+			self.previous_frame = self.next_frame;
+			self.next_frame = self.frame_consumer.pop().unwrap_or(self.next_frame);
+			self.resample_position -= 1.0;
+		}
+		let frame = self.previous_frame
+			+ (self.next_frame - self.previous_frame) * (self.resample_position as f32);
+		frame * (self.volume.value() as f32)
+	}
+}
@@ -1,12 +1,15 @@
 use indexmap::IndexMap;
 
-use crate::{CachedValue, Value};
+use crate::{
+	tween::{Tween, Tweenable},
+	CachedValue, Value,
+};
 
 use super::SendTrackId;
 
 #[derive(Debug, Clone)]
 pub struct TrackSends {
-	sends: IndexMap<SendTrackId, CachedValue<f64>>,
+	sends: IndexMap<SendTrackId, TrackSend>,
 }
 
 impl TrackSends {
@@ -22,7 +25,121 @@ impl TrackSends {
 		volume: impl Into<Value<f64>>,
 	) -> Self {
 		self.sends
-			.insert(send_track.into(), CachedValue::new(volume.into(), 1.0));
+			.insert(send_track.into(), TrackSend::new(volume.into()));
 		self
 	}
+
+	/// Sets the volume of an existing send. A fixed volume is ramped to over
+	/// `tween` rather than jumping immediately; any other `Value` takes effect
+	/// at once and `tween` is ignored. Has no effect if `send_track` wasn't
+	/// added to this track.
+	pub fn set_volume(
+		&mut self,
+		send_track: impl Into<SendTrackId>,
+		volume: impl Into<Value<f64>>,
+		tween: Tween,
+	) {
+		if let Some(send) = self.sends.get_mut(&send_track.into()) {
+			send.set_volume(volume.into(), tween);
+		}
+	}
+
+	/// Removes a send, so audio stops being routed to that track.
+	pub fn remove(&mut self, send_track: impl Into<SendTrackId>) {
+		self.sends.remove(&send_track.into());
+	}
+
+	/// Returns the current volume of a send, if it exists.
+	pub fn volume(&self, send_track: impl Into<SendTrackId>) -> Option<f64> {
+		self.sends
+			.get(&send_track.into())
+			.map(|send| send.value.value())
+	}
+
+	pub(crate) fn update(&mut self, dt: f64) {
+		for send in self.sends.values_mut() {
+			send.update(dt);
+		}
+	}
+
+	pub(crate) fn iter(&self) -> impl Iterator<Item = (&SendTrackId, &CachedValue<f64>)> {
+		self.sends
+			.iter()
+			.map(|(send_track, send)| (send_track, &send.value))
+	}
+}
+
+impl Default for TrackSends {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// A single send's current volume and, if it's mid-ramp, the state needed to
+/// keep tweening it towards its target each tick.
+#[derive(Debug, Clone)]
+struct TrackSend {
+	value: CachedValue<f64>,
+	tweening: Option<Tweening>,
+}
+
+#[derive(Debug, Clone)]
+struct Tweening {
+	from: f64,
+	to: f64,
+	tween: Tween,
+	elapsed: f64,
+}
+
+impl TrackSend {
+	fn new(volume: Value<f64>) -> Self {
+		Self {
+			value: CachedValue::new(volume, 1.0),
+			tweening: None,
+		}
+	}
+
+	fn set_volume(&mut self, volume: Value<f64>, tween: Tween) {
+		match volume {
+			// a fixed target ramps smoothly from the current volume over
+			// `tween`, rather than jumping straight to it.
+			Value::Fixed(to) => {
+				self.tweening = Some(Tweening {
+					from: self.value.value(),
+					to,
+					tween,
+					elapsed: 0.0,
+				});
+			}
+			// a parameter-linked target should track the parameter's value
+			// live, the same way modulation always has; tweening a moving
+			// target doesn't make sense, so any in-progress ramp is cancelled.
+			_ => {
+				self.tweening = None;
+				self.value.set(volume);
+			}
+		}
+	}
+
+	fn update(&mut self, dt: f64) {
+		self.value.update();
+		let Some(tweening) = &mut self.tweening else {
+			return;
+		};
+		tweening.elapsed += dt;
+		let duration = tweening.tween.duration.as_secs_f64();
+		if duration <= 0.0 || tweening.elapsed >= duration {
+			let to = tweening.to;
+			self.value.set(Value::Fixed(to));
+			self.tweening = None;
+			return;
+		}
+		// `Tween::value` turns the raw elapsed fraction into an eased progress
+		// (applying the tween's start delay and easing curve), which
+		// `Tweenable::lerp` then uses the same way every other tweened
+		// parameter in the engine does.
+		let progress = tweening.tween.value(tweening.elapsed / duration);
+		let amount = Tweenable::lerp(tweening.from, tweening.to, progress);
+		self.value.set(Value::Fixed(amount));
+	}
 }
@@ -0,0 +1,44 @@
+/// A lightweight, serializable capture of an instance's playback position, so
+/// a game can store it in a save file and restore it later.
+///
+/// This is the Lua/save-file-facing counterpart of the streaming engine's own
+/// `PlaybackSnapshot` (`crates/kira/src/sound/streaming/sound/decode_scheduler.rs`),
+/// which tracks position as a sample frame index rather than seconds. Use
+/// [`PlaybackSnapshot::from_frames`]/[`PlaybackSnapshot::to_frames`] to
+/// convert between the two explicitly — the units don't convert
+/// automatically, and a mismatched sample rate silently corrupts the resume
+/// point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlaybackSnapshot {
+	/// The playback position, in seconds.
+	pub position: f64,
+	/// Whether playback had already entered its loop region at least once.
+	pub loop_state: bool,
+	/// Whether a one-shot intro (if the sound has one) had already finished
+	/// playing, so a restored sound resumes in the loop rather than
+	/// replaying the intro.
+	pub intro_done: bool,
+}
+
+impl PlaybackSnapshot {
+	/// Builds a snapshot from the streaming engine's frame-indexed
+	/// representation, converting its sample frame position into seconds.
+	/// `sample_rate` must be the rate the frame position was measured
+	/// against (the decoder's sample rate) — using the wrong one silently
+	/// corrupts the resume point.
+	pub fn from_frames(position: i64, loop_state: bool, intro_done: bool, sample_rate: u32) -> Self {
+		Self {
+			position: position as f64 / sample_rate as f64,
+			loop_state,
+			intro_done,
+		}
+	}
+
+	/// Converts this snapshot's seconds-based position back into a sample
+	/// frame index for the streaming engine, the inverse of
+	/// [`PlaybackSnapshot::from_frames`]. `sample_rate` must match the one
+	/// `from_frames` was built with.
+	pub fn to_frames(&self, sample_rate: u32) -> i64 {
+		(self.position * sample_rate as f64).round() as i64
+	}
+}